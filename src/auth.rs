@@ -1,6 +1,7 @@
-use std::sync::LazyLock;
+use std::{collections::HashMap, sync::LazyLock};
 
 use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
 use http::header::ACCEPT;
 use octocrab::auth::OAuth;
 use oo7::Keyring;
@@ -10,7 +11,9 @@ use serde::{Deserialize, Serialize, Serializer};
 static GITHUB_CLIENT_ID: LazyLock<SecretString> =
     LazyLock::new(|| SecretString::from(include_str!("../client_id.txt").trim()));
 const GITHUB_OAUTH_SCOPE: &[&str] = &[""];
-const KEYRING_ATTRIBUTES: &[(&str, &str)] = &[("tool", env!("CARGO_BIN_NAME"))];
+static KEYRING_ATTRIBUTES: LazyLock<HashMap<&str, &str>> =
+    LazyLock::new(|| HashMap::from([("tool", env!("CARGO_BIN_NAME"))]));
+const GITHUB_OAUTH_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
 
 pub(crate) async fn get_oauth() -> Result<OAuth> {
     let keyring = oo7::Keyring::new().await.context("create keyring")?;
@@ -31,7 +34,7 @@ pub(crate) async fn get_oauth() -> Result<OAuth> {
     keyring
         .create_item(
             "OAuth secret",
-            &KEYRING_ATTRIBUTES,
+            &*KEYRING_ATTRIBUTES,
             serde_json::to_vec(&oauth).context("serialize OAuth")?,
             true,
         )
@@ -42,13 +45,13 @@ pub(crate) async fn get_oauth() -> Result<OAuth> {
 
 async fn get_oauth_from_keyring(keyring: &Keyring) -> Result<Option<OAuth>> {
     // Find a stored secret
-    let items = keyring.search_items(&KEYRING_ATTRIBUTES).await?;
+    let items = keyring.search_items(&*KEYRING_ATTRIBUTES).await?;
     match items.as_slice() {
         [] => Ok(None),
         [item] => {
             // secret found, load it
             let secret = item.secret().await.context("retrieve secret")?;
-            let s = str::from_utf8(secret.as_bytes()).context("decode secret string")?;
+            let s = str::from_utf8(&secret).context("decode secret string")?;
 
             let Ok(oauth) = serde_json::from_str::<OAuthWrapper>(s) else {
                 eprintln!("oauth serialization format changed");
@@ -63,12 +66,40 @@ async fn get_oauth_from_keyring(keyring: &Keyring) -> Result<Option<OAuth>> {
                 return Ok(None);
             }
 
-            if oauth.expires_in.is_some() {
-                eprintln!("oauth token potentially expired");
+            if !oauth.access_token_expired() {
+                return Ok(Some(oauth.into()));
+            }
+
+            eprintln!("oauth token expired, trying to refresh");
+
+            if oauth.refresh_token_expired() {
+                eprintln!("oauth refresh token also expired");
+                return Ok(None);
+            }
+
+            let Some(refresh_token) = &oauth.refresh_token else {
+                eprintln!("oauth token expired and no refresh token is available");
                 return Ok(None);
+            };
+
+            let refreshed = match refresh_oauth(refresh_token).await {
+                Ok(refreshed) => refreshed,
+                Err(err) => {
+                    eprintln!("refreshing oauth token failed: {err:#}");
+                    return Ok(None);
+                }
+            };
+
+            // Overwrite the stored secret: oo7 items are immutable, so delete and recreate.
+            // Persisting is best-effort: the refreshed token is already good for this run, so
+            // a transient keyring failure here shouldn't fail the whole invocation, only cost
+            // us a re-refresh next time (or a full re-auth, if the stale secret was already
+            // deleted or the refresh token also expires).
+            if let Err(err) = persist_refreshed_oauth(keyring, item, &refreshed).await {
+                eprintln!("failed to persist refreshed OAuth secret: {err:#}");
             }
 
-            Ok(Some(oauth.into()))
+            Ok(Some(refreshed.into()))
         }
         _ => {
             bail!("multiple OAuth secrets found")
@@ -76,6 +107,48 @@ async fn get_oauth_from_keyring(keyring: &Keyring) -> Result<Option<OAuth>> {
     }
 }
 
+/// Overwrite the stored OAuth secret: oo7 items are immutable, so delete and recreate.
+async fn persist_refreshed_oauth(
+    keyring: &Keyring,
+    item: &oo7::Item,
+    refreshed: &OAuthWrapper,
+) -> Result<()> {
+    item.delete().await.context("delete stale OAuth secret")?;
+    keyring
+        .create_item(
+            "OAuth secret",
+            &*KEYRING_ATTRIBUTES,
+            serde_json::to_vec(refreshed).context("serialize refreshed OAuth")?,
+            true,
+        )
+        .await
+        .context("store refreshed OAuth secret")?;
+    Ok(())
+}
+
+/// Exchange a still-valid refresh token for a new access token.
+async fn refresh_oauth(refresh_token: &SecretString) -> Result<OAuthWrapper> {
+    let client = reqwest::Client::new();
+    let oauth: OAuth = client
+        .post(GITHUB_OAUTH_TOKEN_URL)
+        .header(ACCEPT, "application/json")
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("client_id", GITHUB_CLIENT_ID.expose_secret()),
+            ("refresh_token", refresh_token.expose_secret()),
+        ])
+        .send()
+        .await
+        .context("send refresh token request")?
+        .error_for_status()
+        .context("refresh token request failed")?
+        .json()
+        .await
+        .context("parse refreshed OAuth response")?;
+
+    Ok(OAuthWrapper::from(oauth))
+}
+
 async fn perform_oauth() -> Result<OAuth> {
     let oc = octocrab::Octocrab::builder()
         .base_uri("https://github.com")?
@@ -108,6 +181,30 @@ struct OAuthWrapper {
     #[serde(serialize_with = "serialize_opt_secret_string")]
     refresh_token: Option<SecretString>,
     refresh_token_expires_in: Option<usize>,
+    /// When this token was obtained, so `expires_in`/`refresh_token_expires_in` (both
+    /// relative, in seconds) can be turned into absolute deadlines.
+    issued_at: DateTime<Utc>,
+}
+
+impl OAuthWrapper {
+    fn access_token_expired(&self) -> bool {
+        match self.expires_in {
+            Some(expires_in) => {
+                self.issued_at + chrono::Duration::seconds(expires_in as i64) <= Utc::now()
+            }
+            None => false,
+        }
+    }
+
+    fn refresh_token_expired(&self) -> bool {
+        match self.refresh_token_expires_in {
+            Some(expires_in) => {
+                self.issued_at + chrono::Duration::seconds(expires_in as i64) <= Utc::now()
+            }
+            // No known expiry: assume it is still usable and let the refresh call itself fail otherwise.
+            None => false,
+        }
+    }
 }
 
 impl From<OAuth> for OAuthWrapper {
@@ -119,6 +216,7 @@ impl From<OAuth> for OAuthWrapper {
             expires_in: oauth.expires_in,
             refresh_token: oauth.refresh_token,
             refresh_token_expires_in: oauth.refresh_token_expires_in,
+            issued_at: Utc::now(),
         }
     }
 }
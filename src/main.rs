@@ -1,31 +1,45 @@
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
     sync::LazyLock,
     time::Duration,
 };
 
-use anyhow::{Context, Error, Result, bail};
+use anyhow::{Context, Error, Result};
 use chrono::Utc;
 use clap::Parser;
-use octocrab::models::events::payload::{
-    EventPayload, IssueCommentEventAction, IssuesEventAction, PullRequestEventAction,
+use directories::ProjectDirs;
+use futures::stream::{FuturesUnordered, StreamExt};
+use octocrab::{
+    Page,
+    models::events::{
+        Event,
+        payload::{
+            EventPayload, IssueCommentEventAction, IssuesEventAction, PullRequestEventAction,
+        },
+    },
 };
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+mod auth;
+
+/// Max page size that the GitHub events API accepts.
+const EVENTS_PER_PAGE: u8 = 100;
 
 static UNSAFE_CHARS: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r#"[^0-9a-zA-Z /():;.&+-]"#).expect("valid regex"));
 static WHITESPACE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\s+"#).expect("valid regex"));
 
+#[cfg(feature = "summarize")]
+mod summarize;
+
 #[derive(Parser)]
 struct Args {
     /// Event cutoff by creation date.
     #[clap(long, default_value="1 week", value_parser=humantime::parse_duration)]
     event_cutoff: Duration,
 
-    /// Number of events to fetch.
-    #[clap(long, default_value_t = 1000)]
-    n_events: u64,
-
     /// Include organizations.
     ///
     /// Defaults to "all" if not specified.
@@ -55,6 +69,32 @@ struct Args {
     /// User access token.
     #[clap(long, env = "GITHUB_USER_ACCESS_TOKEN")]
     user_access_token: Option<String>,
+
+    /// Output format.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Markdown)]
+    format: OutputFormat,
+
+    /// Generate an additional natural-language summary paragraph via an OpenAI-compatible
+    /// chat completion endpoint.
+    ///
+    /// Configure `SUMMARIZE_API_KEY` (required), `SUMMARIZE_BASE_URL` and `SUMMARIZE_MODEL`
+    /// (both optional, for self-hosted models). Falls back to the plain digest if no API
+    /// key is configured. Only applies to `--format markdown`, so `json`/`table` output
+    /// stays machine-readable. Requires the `summarize` feature.
+    #[cfg(feature = "summarize")]
+    #[clap(long)]
+    summarize: bool,
+}
+
+#[derive(Debug, Clone, Default, clap::ValueEnum)]
+enum OutputFormat {
+    /// Hand-rolled Markdown bullet list (default, human-readable).
+    #[default]
+    Markdown,
+    /// Structured JSON document, for piping into other tooling.
+    Json,
+    /// Aligned plain-text table.
+    Table,
 }
 
 #[tokio::main]
@@ -66,27 +106,22 @@ async fn main() -> Result<()> {
     let mut oc_builder = octocrab::Octocrab::builder();
     if let Some(token) = args.user_access_token {
         oc_builder = oc_builder.user_access_token(token);
+    } else {
+        let oauth = auth::get_oauth().await.context("get OAuth token")?;
+        oc_builder = oc_builder.user_access_token(oauth.access_token);
     }
     let oc = oc_builder.build().context("create octocrap instance")?;
 
-    let events: Vec<octocrab::models::events::Event> = oc
-        .get(
-            format!("/users/{}/events", args.username),
-            Some(&[("per_page", args.n_events)]),
-        )
+    let events = list_events_until(&oc, &args.username, created_at)
         .await
         .context("list events")?;
 
-    if !events.iter().any(|evt| evt.created_at < created_at) {
-        bail!(
-            "number of events ({}) to low for give time period ({})",
-            args.n_events,
-            humantime::format_duration(args.event_cutoff)
-        );
-    }
-
     let mut interactions_by_repo: BTreeMap<Repo, BTreeMap<Topic, BTreeSet<Action>>> =
         Default::default();
+    // `PushEvent`s arrive one-per-push, but readers care about "how much got pushed to this
+    // branch this week", not a line per push, so commits are tallied here and folded into a
+    // single topic per repo/branch once the event loop is done.
+    let mut push_commits: BTreeMap<(Repo, String), PushTally> = Default::default();
     for event in events {
         if !event.public {
             continue;
@@ -217,6 +252,101 @@ async fn main() -> Result<()> {
                 Topic::try_from(evt.pull_request).context("convert PR data")?,
                 Action::Review,
             ),
+            EventPayload::PushEvent(evt) => {
+                let branch = evt
+                    .r#ref
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(&evt.r#ref)
+                    .to_owned();
+                // `commits` is capped at 20 entries by GitHub regardless of how many were
+                // actually pushed; `distinct_size` carries the true count.
+                let n_commits = evt.distinct_size;
+                let head_sha = evt.head;
+
+                // Events are paginated newest-first, so the first push seen for a given
+                // repo/branch is the most recent one: only that push's head SHA is kept.
+                push_commits
+                    .entry((repo.clone(), branch))
+                    .or_insert_with(|| PushTally {
+                        n_commits: 0,
+                        head_sha,
+                    })
+                    .n_commits += n_commits;
+
+                continue;
+            }
+            EventPayload::CreateEvent(evt) => {
+                let kind = match evt.ref_type.as_str() {
+                    "branch" => "branch",
+                    "tag" => "tag",
+                    _ => continue,
+                };
+                let Some(ref_name) = evt.r#ref else {
+                    continue;
+                };
+
+                let topic = Topic {
+                    url: format!("https://github.com/{}/tree/{ref_name}", repo.name),
+                    number: None,
+                    title: format!("created {kind} {ref_name}"),
+                };
+
+                (topic, Action::Branch)
+            }
+            EventPayload::DeleteEvent(evt) => {
+                let kind = match evt.ref_type.as_str() {
+                    "branch" => "branch",
+                    "tag" => "tag",
+                    _ => continue,
+                };
+
+                let topic = Topic {
+                    url: format!("https://github.com/{}", repo.name),
+                    number: None,
+                    title: format!("deleted {kind} {}", evt.r#ref),
+                };
+
+                (topic, Action::Branch)
+            }
+            EventPayload::ReleaseEvent(evt) => {
+                let topic = Topic {
+                    url: evt.release.html_url.to_string(),
+                    number: None,
+                    title: evt.release.tag_name,
+                };
+
+                (topic, Action::Release)
+            }
+            EventPayload::ForkEvent(evt) => {
+                let topic = Topic {
+                    url: evt
+                        .forkee
+                        .html_url
+                        .map(|url| url.to_string())
+                        .unwrap_or_default(),
+                    number: None,
+                    title: evt.forkee.full_name.unwrap_or(evt.forkee.name),
+                };
+
+                (topic, Action::Fork)
+            }
+            EventPayload::CommitCommentEvent(evt) => {
+                // `Comment` has no `commit_id` field; the SHA is the last path segment of
+                // the comment's own `html_url` (".../commit/<sha>#commitcomment-<id>").
+                let commit_sha = evt
+                    .comment
+                    .html_url
+                    .path_segments()
+                    .and_then(|mut segments| segments.next_back())
+                    .unwrap_or_default();
+                let topic = Topic {
+                    url: evt.comment.html_url.to_string(),
+                    number: None,
+                    title: format!("commit {}", commit_sha.get(..7).unwrap_or(commit_sha)),
+                };
+
+                (topic, Action::Comment)
+            }
             _ => {
                 continue;
             }
@@ -229,38 +359,306 @@ async fn main() -> Result<()> {
             .insert(action);
     }
 
+    for ((repo, branch), tally) in push_commits {
+        let topic = Topic {
+            url: format!("https://github.com/{}/commit/{}", repo.name, tally.head_sha),
+            number: None,
+            title: format!("{} commit(s) to {branch}", tally.n_commits),
+        };
+
+        interactions_by_repo
+            .entry(repo)
+            .or_default()
+            .entry(topic)
+            .or_default()
+            .insert(Action::Push);
+    }
+
+    let mut html_urls = fetch_repo_html_urls(interactions_by_repo.keys().map(|repo| repo.url.clone()))
+        .await
+        .context("fetch repo metadata")?;
+
+    let mut repos = Vec::with_capacity(interactions_by_repo.len());
     for (repo, topics) in interactions_by_repo.into_iter() {
-        let gh_repo: octocrab::models::Repository = octocrab::instance()
-            .get(&repo.url, None::<&()>)
-            .await
-            .with_context(|| format!("get repo: {}", repo.url))?;
+        let html_url = html_urls
+            .remove(&repo.url)
+            .with_context(|| format!("missing resolved repo URL for: {}", repo.url))?;
+
+        let topics = topics
+            .into_iter()
+            .map(|(topic, actions)| TopicSummary {
+                topic,
+                actions: actions.into_iter().collect(),
+            })
+            .collect();
+
+        repos.push(RepoSummary {
+            repo,
+            html_url,
+            topics,
+        });
+    }
 
-        print!(
-            "- *[{}]({}):*",
-            repo.name,
-            gh_repo.html_url.context("no html URL for repo")?
-        );
+    // The prose summary is only ever mixed into the human-readable Markdown output; `json`
+    // and `table` are meant to be piped into other tooling and must stay free of it.
+    #[cfg(feature = "summarize")]
+    if args.summarize && matches!(args.format, OutputFormat::Markdown) {
+        match summarize::summarize(&repos).await {
+            Ok(Some(summary)) => println!("{summary}\n"),
+            Ok(None) => eprintln!("--summarize: no API key configured, falling back to the plain digest"),
+            Err(err) => eprintln!("--summarize: failed to generate summary: {err:#}"),
+        }
+    }
 
-        for (topic_idx, (topic, actions)) in topics.into_iter().enumerate() {
+    match args.format {
+        OutputFormat::Markdown => print_markdown(&repos),
+        OutputFormat::Json => print_json(&repos)?,
+        OutputFormat::Table => print_table(&repos),
+    }
+
+    Ok(())
+}
+
+fn print_markdown(repos: &[RepoSummary]) {
+    for repo in repos {
+        print!("- *[{}]({}):*", repo.repo.name, repo.html_url);
+
+        for (topic_idx, topic) in repo.topics.iter().enumerate() {
             if topic_idx > 0 {
                 print!(",");
             }
             // EN space
             print!("\u{2000}");
 
-            for action in actions.into_iter() {
+            for action in &topic.actions {
                 print!("{action}");
             }
-            print!(" {topic}");
+            print!(" {}", topic.topic);
         }
 
         println!();
     }
+}
 
+fn print_json(repos: &[RepoSummary]) -> Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(repos).context("serialize summary as JSON")?
+    );
     Ok(())
 }
 
-#[derive(Debug)]
+fn print_table(repos: &[RepoSummary]) {
+    struct Row {
+        repo: String,
+        topic_number: String,
+        title: String,
+        actions: String,
+    }
+
+    let mut rows = Vec::new();
+    for repo in repos {
+        for topic in &repo.topics {
+            rows.push(Row {
+                repo: repo.repo.name.clone(),
+                topic_number: topic
+                    .topic
+                    .number
+                    .map(|number| format!("#{number}"))
+                    .unwrap_or_default(),
+                title: topic.topic.title.clone(),
+                actions: topic.actions.iter().map(|action| action.as_str()).collect(),
+            });
+        }
+    }
+
+    let repo_width = rows
+        .iter()
+        .map(|row| row.repo.len())
+        .max()
+        .unwrap_or_default()
+        .max("REPO".len());
+    let topic_width = rows
+        .iter()
+        .map(|row| row.topic_number.len())
+        .max()
+        .unwrap_or_default()
+        .max("TOPIC".len());
+    let title_width = rows
+        .iter()
+        .map(|row| row.title.len())
+        .max()
+        .unwrap_or_default()
+        .max("TITLE".len());
+
+    println!(
+        "{:<repo_width$}  {:<topic_width$}  {:<title_width$}  ACTIONS",
+        "REPO", "TOPIC", "TITLE"
+    );
+    for row in rows {
+        println!(
+            "{:<repo_width$}  {:<topic_width$}  {:<title_width$}  {}",
+            row.repo, row.topic_number, row.title, row.actions
+        );
+    }
+}
+
+/// Walk the `/users/{username}/events` pages, accumulating events until either an event
+/// older than `created_at` is seen or GitHub runs out of pages (it only exposes ~300 recent
+/// events in total, regardless of `per_page`).
+async fn list_events_until(
+    oc: &octocrab::Octocrab,
+    username: &str,
+    created_at: chrono::DateTime<Utc>,
+) -> Result<Vec<Event>> {
+    let mut events = Vec::new();
+
+    let mut page: Page<Event> = oc
+        .get(
+            format!("/users/{username}/events"),
+            Some(&[("per_page", EVENTS_PER_PAGE)]),
+        )
+        .await
+        .context("list events")?;
+
+    loop {
+        let mut reached_cutoff = false;
+        for event in page.items {
+            reached_cutoff = event.created_at < created_at;
+            events.push(event);
+            if reached_cutoff {
+                break;
+            }
+        }
+
+        if reached_cutoff {
+            break;
+        }
+
+        let Some(next) = oc
+            .get_page(&page.next)
+            .await
+            .context("get next events page")?
+        else {
+            eprintln!(
+                "ran out of events before reaching the cutoff ({})",
+                humantime::format_duration(
+                    Utc::now()
+                        .signed_duration_since(created_at)
+                        .to_std()
+                        .unwrap_or_default()
+                )
+            );
+            break;
+        };
+        page = next;
+    }
+
+    Ok(events)
+}
+
+/// How long a cached repo's `html_url` is trusted before it's re-fetched.
+fn repo_cache_ttl() -> chrono::Duration {
+    chrono::Duration::hours(1)
+}
+
+/// Max number of repo metadata requests in flight at once.
+const MAX_CONCURRENT_REPO_FETCHES: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedRepo {
+    html_url: String,
+    fetched_at: chrono::DateTime<Utc>,
+}
+
+/// Resolve each repo API URL to its `html_url`, concurrently (bounded) and backed by an
+/// on-disk cache under the user cache dir, so repeated invocations skip the network
+/// entirely for repos seen within [`repo_cache_ttl`].
+async fn fetch_repo_html_urls(
+    urls: impl Iterator<Item = String>,
+) -> Result<HashMap<String, String>> {
+    let cache_path = repo_cache_path();
+    let mut cache = match &cache_path {
+        Some(path) => load_repo_cache(path),
+        None => Default::default(),
+    };
+
+    let mut resolved = HashMap::new();
+    let mut pending = Vec::new();
+    for url in urls {
+        match cache.get(&url) {
+            Some(cached) if Utc::now() - cached.fetched_at < repo_cache_ttl() => {
+                resolved.insert(url, cached.html_url.clone());
+            }
+            _ => pending.push(url),
+        }
+    }
+
+    let mut pending = pending.into_iter();
+    let mut in_flight: FuturesUnordered<_> = pending
+        .by_ref()
+        .take(MAX_CONCURRENT_REPO_FETCHES)
+        .map(fetch_repo_html_url)
+        .collect();
+
+    let mut cache_dirty = false;
+    while let Some((url, html_url)) = in_flight.next().await.transpose()? {
+        cache.insert(
+            url.clone(),
+            CachedRepo {
+                html_url: html_url.clone(),
+                fetched_at: Utc::now(),
+            },
+        );
+        cache_dirty = true;
+        resolved.insert(url, html_url);
+
+        if let Some(next_url) = pending.next() {
+            in_flight.push(fetch_repo_html_url(next_url));
+        }
+    }
+
+    if cache_dirty
+        && let Some(path) = &cache_path
+        && let Err(err) = save_repo_cache(path, &cache)
+    {
+        eprintln!("failed to persist repo metadata cache: {err:#}");
+    }
+
+    Ok(resolved)
+}
+
+async fn fetch_repo_html_url(url: String) -> Result<(String, String)> {
+    let gh_repo: octocrab::models::Repository = octocrab::instance()
+        .get(&url, None::<&()>)
+        .await
+        .with_context(|| format!("get repo: {url}"))?;
+    let html_url = gh_repo.html_url.context("no html URL for repo")?.to_string();
+    Ok((url, html_url))
+}
+
+fn repo_cache_path() -> Option<PathBuf> {
+    let dirs = ProjectDirs::from("", "", env!("CARGO_BIN_NAME"))?;
+    Some(dirs.cache_dir().join("repo_metadata.json"))
+}
+
+fn load_repo_cache(path: &Path) -> HashMap<String, CachedRepo> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_repo_cache(path: &Path, cache: &HashMap<String, CachedRepo>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("create cache dir")?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(cache).context("serialize repo cache")?)
+        .context("write repo cache")?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
 struct Repo {
     name: String,
     url: String,
@@ -286,16 +684,18 @@ impl PartialOrd for Repo {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 struct Topic {
     url: String,
-    number: u64,
+    /// Issue/PR number, if this topic has one. Non-issue/PR activity (pushes, branches,
+    /// releases, forks, ...) has no number and is identified by `url` and `title`.
+    number: Option<u64>,
     title: String,
 }
 
 impl PartialEq<Topic> for Topic {
     fn eq(&self, other: &Topic) -> bool {
-        self.number == other.number
+        self.cmp(other).is_eq()
     }
 }
 
@@ -303,7 +703,18 @@ impl Eq for Topic {}
 
 impl Ord for Topic {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.number.cmp(&other.number)
+        // Numbered topics (issues/PRs) must sort numerically, not by their URL's trailing
+        // number as a string (`"10" < "2"` lexicographically). Number-less topics (pushes,
+        // branches, releases, ...) fall back to the URL and then the title, since some of them
+        // share a URL (every deleted branch/tag links to the repo root).
+        let key = |topic: &Self| {
+            (
+                topic.number,
+                topic.url.clone(),
+                topic.number.is_none().then(|| topic.title.clone()),
+            )
+        };
+        key(self).cmp(&key(other))
     }
 }
 
@@ -320,7 +731,10 @@ impl std::fmt::Display for Topic {
         let title = UNSAFE_CHARS.replace_all(title, "");
         let title = WHITESPACE.replace_all(&title, " ");
 
-        write!(f, "[#{number}]({url}) (_{title}_)")
+        match number {
+            Some(number) => write!(f, "[#{number}]({url}) (_{title}_)"),
+            None => write!(f, "[{title}]({url})"),
+        }
     }
 }
 
@@ -328,7 +742,7 @@ impl From<octocrab::models::issues::Issue> for Topic {
     fn from(issue: octocrab::models::issues::Issue) -> Self {
         Self {
             url: issue.html_url.to_string(),
-            number: issue.number,
+            number: Some(issue.number),
             title: issue.title,
         }
     }
@@ -340,29 +754,45 @@ impl TryFrom<octocrab::models::pulls::PullRequest> for Topic {
     fn try_from(pr: octocrab::models::pulls::PullRequest) -> Result<Self, Self::Error> {
         Ok(Self {
             url: pr.html_url.context("HTML URL missing")?.to_string(),
-            number: pr.number,
+            number: Some(pr.number),
             title: pr.title.context("PR title missing")?,
         })
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// Running commit count for a `PushEvent` stream on a single repo/branch, folded into one
+/// [`Topic`] once the event loop finishes.
+#[derive(Debug, Default)]
+struct PushTally {
+    n_commits: u64,
+    head_sha: String,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 enum Action {
     Code,
+    Push,
     Write,
+    Release,
     Review,
     Comment,
     Assist,
+    Branch,
+    Fork,
 }
 
 impl Action {
     fn as_str(&self) -> &'static str {
         match self {
             Action::Code => "🔨",
+            Action::Push => "⬆️",
             Action::Write => "✍️",
+            Action::Release => "🚀",
             Action::Review => "🕵️",
             Action::Comment => "💬",
             Action::Assist => "⚙️",
+            Action::Branch => "🌿",
+            Action::Fork => "🍴",
         }
     }
 }
@@ -372,3 +802,19 @@ impl std::fmt::Display for Action {
         write!(f, "{}", self.as_str())
     }
 }
+
+/// A repo plus its resolved web URL, ready to be rendered in any [`OutputFormat`].
+#[derive(Debug, Serialize)]
+struct RepoSummary {
+    #[serde(flatten)]
+    repo: Repo,
+    html_url: String,
+    topics: Vec<TopicSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct TopicSummary {
+    #[serde(flatten)]
+    topic: Topic,
+    actions: Vec<Action>,
+}
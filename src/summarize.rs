@@ -0,0 +1,113 @@
+//! Optional natural-language summary of the activity digest via an OpenAI-compatible chat
+//! completion endpoint. `reqwest` and `serde_json` are already mandatory dependencies for
+//! the OAuth and repo-metadata-cache paths, so the `summarize` feature doesn't buy dependency
+//! weight here; it exists to keep the extra CLI flag, prompt-building, and outbound chat
+//! completion call opt-in rather than part of the default run.
+
+use anyhow::{Context, Result};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+
+use crate::RepoSummary;
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+/// Ask the configured chat completion endpoint for a short prose summary of `repos`.
+///
+/// Returns `Ok(None)` if `SUMMARIZE_API_KEY` isn't set, so callers can fall back to the
+/// plain digest instead of failing the whole run.
+pub(crate) async fn summarize(repos: &[RepoSummary]) -> Result<Option<String>> {
+    let Ok(api_key) = std::env::var("SUMMARIZE_API_KEY").map(SecretString::from) else {
+        return Ok(None);
+    };
+    let base_url =
+        std::env::var("SUMMARIZE_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+    let model = std::env::var("SUMMARIZE_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+
+    let request = ChatCompletionRequest {
+        model,
+        messages: vec![
+            ChatMessage {
+                role: "system",
+                content: "You turn a developer's GitHub activity digest into a short, \
+                    friendly weekly summary paragraph. Group related activity by theme \
+                    rather than listing repo-by-repo."
+                    .to_string(),
+            },
+            ChatMessage {
+                role: "user",
+                content: build_prompt(repos),
+            },
+        ],
+    };
+
+    let response: ChatCompletionResponse = reqwest::Client::new()
+        .post(format!("{base_url}/chat/completions"))
+        .bearer_auth(api_key.expose_secret())
+        .json(&request)
+        .send()
+        .await
+        .context("send chat completion request")?
+        .error_for_status()
+        .context("chat completion request failed")?
+        .json()
+        .await
+        .context("parse chat completion response")?;
+
+    let summary = response
+        .choices
+        .into_iter()
+        .next()
+        .context("chat completion returned no choices")?
+        .message
+        .content;
+
+    Ok(Some(summary))
+}
+
+fn build_prompt(repos: &[RepoSummary]) -> String {
+    let mut prompt = String::from("Here is this week's GitHub activity digest:\n\n");
+
+    for repo in repos {
+        prompt.push_str(&format!("# {}\n", repo.repo.name));
+        for topic in &repo.topics {
+            let actions = topic
+                .actions
+                .iter()
+                .map(|action| format!("{action:?}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            prompt.push_str(&format!("- [{actions}] {}\n", topic.topic.title));
+        }
+    }
+
+    prompt
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    content: String,
+}